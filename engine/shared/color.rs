@@ -35,6 +35,13 @@ define_colors! {
     0xff800080 => PURPLE,
     0xffff00ff => FUCHSIA,
     0xff00ffff => CYAN,
+    0xffffff00 => YELLOW,
+    0xffff00ff => MAGENTA,
+}
+
+impl RGBA {
+    /// Fully transparent black.
+    pub const TRANSPARENT: Self = Self::new(0, 0, 0, 0);
 }
 
 macro_rules! impl_get_set {
@@ -292,6 +299,22 @@ impl From<[u8; 4]> for RGBA {
     }
 }
 
+/// Converts a packed `0xRRGGBBAA` value, red in the most significant byte.
+impl From<u32> for RGBA {
+    fn from(value: u32) -> Self {
+        let [r, g, b, a] = value.to_be_bytes();
+        Self::new(r, g, b, a)
+    }
+}
+
+/// Converts normalized components, each value is clamped to `0.0..=1.0`.
+impl From<[f32; 4]> for RGBA {
+    fn from(value: [f32; 4]) -> Self {
+        let [r, g, b, a] = value.map(|i| (i.clamp(0.0, 1.0) * 255.0 + 0.5) as u8);
+        Self::new(r, g, b, a)
+    }
+}
+
 impl From<RGB> for RGBA {
     fn from(color: RGB) -> RGBA {
         let [r, g, b] = color.to_bytes();
@@ -454,6 +477,26 @@ mod tests {
         assert_eq!("#01020304", RGBA::new(1, 2, 3, 4).to_string());
     }
 
+    #[test]
+    fn named_colors() {
+        assert_eq!(RGB::YELLOW, RGB::new(255, 255, 0));
+        assert_eq!(RGB::MAGENTA, RGB::new(255, 0, 255));
+        assert_eq!(RGBA::YELLOW, RGBA::new(255, 255, 0, 255));
+        assert_eq!(RGBA::MAGENTA, RGBA::new(255, 0, 255, 255));
+        assert_eq!(RGBA::CYAN, RGBA::new(0, 255, 255, 255));
+        assert_eq!(RGBA::TRANSPARENT.a(), 0);
+    }
+
+    #[test]
+    fn rgba_from() {
+        assert_eq!(RGBA::from(0x11223344), RGBA::new(0x11, 0x22, 0x33, 0x44));
+        assert_eq!(
+            RGBA::from([1.0, 0.0, 0.5, 1.0]),
+            RGBA::new(255, 0, 128, 255)
+        );
+        assert_eq!(RGBA::from([2.0, -1.0, 0.0, 0.0]), RGBA::new(255, 0, 0, 0));
+    }
+
     #[test]
     fn rgb_scale_color() {
         let c = RGB::from_u32_rgb(0xff601f);
@@ -1,16 +1,21 @@
 use core::{
+    cell::Cell,
     ffi::c_int,
-    mem::{self, MaybeUninit},
+    fmt, mem,
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 use crate::{
-    color::RGBA,
+    cell::Sync,
+    color::{RGB, RGBA},
     ffi::{
         self,
         api::tri::triangleapi_s,
         common::{model_s, vec3_t},
     },
     macros::define_enum_for_primitive,
+    math::sqrtf,
+    misc::Rect,
     render::RenderMode,
 };
 
@@ -28,7 +33,40 @@ define_enum_for_primitive! {
     }
 }
 
+impl Primitive {
+    /// Returns `true` if the primitive can be built from `count` vertices.
+    pub const fn is_valid_vertex_count(self, count: usize) -> bool {
+        match self {
+            Self::Points => count >= 1,
+            Self::Lines => count >= 2 && count % 2 == 0,
+            Self::Triangles => count >= 3 && count % 3 == 0,
+            Self::TriangleStrip | Self::TriangleFan | Self::Polygon => count >= 3,
+            Self::Quads => count >= 4 && count % 4 == 0,
+            Self::QuadStrip => count >= 4 && count % 2 == 0,
+        }
+    }
+}
+
+/// A vertex for [TriangleApi::draw_mesh].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vertex {
+    pub pos: vec3_t,
+    pub uv: (f32, f32),
+    pub color: RGBA,
+}
+
+impl Vertex {
+    pub const fn new(pos: vec3_t, uv: (f32, f32), color: RGBA) -> Self {
+        Self { pos, uv, color }
+    }
+}
+
 define_enum_for_primitive! {
+    /// Face culling style.
+    ///
+    /// The triangle API only supports these two styles. There is no separate back-face style
+    /// and no way to change the front face winding, so to cull the other side of a mesh emit
+    /// its vertices in reverse order.
     #[derive(Copy, Clone, Debug, PartialEq, Eq)]
     pub enum CullFace: u32 {
         None(ffi::api::tri::TRICULLSTYLE_TRI_NONE),
@@ -36,12 +74,173 @@ define_enum_for_primitive! {
     }
 }
 
+define_enum_for_primitive! {
+    /// OpenGL matrix names accepted by [TriangleApi::get_matrix].
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub enum MatrixKind: i32 {
+        /// `GL_MODELVIEW_MATRIX`
+        ModelView(0x0ba6),
+        /// `GL_PROJECTION_MATRIX`
+        Projection(0x0ba7),
+        /// `GL_TEXTURE_MATRIX`
+        Texture(0x0ba8),
+    }
+}
+
+/// A 4x4 matrix stored in column-major order, the layout used by OpenGL.
+///
+/// The element at row `r` and column `c` is stored at index `c * 4 + r`. Points are column
+/// vectors multiplied on the right, so translation is stored at indices 12, 13 and 14.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Matrix4([f32; 16]);
+
+impl Matrix4 {
+    pub const IDENTITY: Self = Self([
+        1.0, 0.0, 0.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0, //
+    ]);
+
+    /// Creates a matrix from an array in column-major order.
+    pub const fn from_cols_array(m: [f32; 16]) -> Self {
+        Self(m)
+    }
+
+    /// Returns the matrix as an array in column-major order.
+    pub const fn to_cols_array(&self) -> [f32; 16] {
+        self.0
+    }
+
+    /// Returns the element at the given row and column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is greater than 3.
+    pub const fn get(&self, row: usize, col: usize) -> f32 {
+        assert!(row < 4 && col < 4);
+        self.0[col * 4 + row]
+    }
+
+    /// Returns a row of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is greater than 3.
+    pub const fn row(&self, row: usize) -> [f32; 4] {
+        [
+            self.get(row, 0),
+            self.get(row, 1),
+            self.get(row, 2),
+            self.get(row, 3),
+        ]
+    }
+
+    /// Returns a column of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `col` is greater than 3.
+    pub const fn col(&self, col: usize) -> [f32; 4] {
+        [
+            self.get(0, col),
+            self.get(1, col),
+            self.get(2, col),
+            self.get(3, col),
+        ]
+    }
+
+    /// Multiplies a 4D column vector by this matrix.
+    pub fn transform(&self, v: [f32; 4]) -> [f32; 4] {
+        let m = &self.0;
+        core::array::from_fn(|r| m[r] * v[0] + m[4 + r] * v[1] + m[8 + r] * v[2] + m[12 + r] * v[3])
+    }
+
+    /// Transforms a point, dividing the result by `w`.
+    pub fn transform_point(&self, p: vec3_t) -> vec3_t {
+        let [x, y, z, w] = self.transform([p.x, p.y, p.z, 1.0]);
+        vec3_t::new(x, y, z) / w
+    }
+
+    /// Returns the inverse of this matrix or `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let m = &self.0;
+
+        let s0 = m[0] * m[5] - m[4] * m[1];
+        let s1 = m[0] * m[6] - m[4] * m[2];
+        let s2 = m[0] * m[7] - m[4] * m[3];
+        let s3 = m[1] * m[6] - m[5] * m[2];
+        let s4 = m[1] * m[7] - m[5] * m[3];
+        let s5 = m[2] * m[7] - m[6] * m[3];
+
+        let c5 = m[10] * m[15] - m[14] * m[11];
+        let c4 = m[9] * m[15] - m[13] * m[11];
+        let c3 = m[9] * m[14] - m[13] * m[10];
+        let c2 = m[8] * m[15] - m[12] * m[11];
+        let c1 = m[8] * m[14] - m[12] * m[10];
+        let c0 = m[8] * m[13] - m[12] * m[9];
+
+        let det = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv = 1.0 / det;
+
+        Some(Self([
+            (m[5] * c5 - m[6] * c4 + m[7] * c3) * inv,
+            (-m[1] * c5 + m[2] * c4 - m[3] * c3) * inv,
+            (m[13] * s5 - m[14] * s4 + m[15] * s3) * inv,
+            (-m[9] * s5 + m[10] * s4 - m[11] * s3) * inv,
+            (-m[4] * c5 + m[6] * c2 - m[7] * c1) * inv,
+            (m[0] * c5 - m[2] * c2 + m[3] * c1) * inv,
+            (-m[12] * s5 + m[14] * s2 - m[15] * s1) * inv,
+            (m[8] * s5 - m[10] * s2 + m[11] * s1) * inv,
+            (m[4] * c4 - m[5] * c2 + m[7] * c0) * inv,
+            (-m[0] * c4 + m[1] * c2 - m[3] * c0) * inv,
+            (m[12] * s4 - m[13] * s2 + m[15] * s0) * inv,
+            (-m[8] * s4 + m[9] * s2 - m[11] * s0) * inv,
+            (-m[4] * c3 + m[5] * c1 - m[6] * c0) * inv,
+            (m[0] * c3 - m[1] * c1 + m[2] * c0) * inv,
+            (-m[12] * s3 + m[13] * s1 - m[14] * s0) * inv,
+            (m[8] * s3 - m[9] * s1 + m[10] * s0) * inv,
+        ]))
+    }
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl core::ops::Mul for Matrix4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut ret = [0.0; 16];
+        for c in 0..4 {
+            for r in 0..4 {
+                ret[c * 4 + r] = (0..4).map(|k| self.0[k * 4 + r] * rhs.0[c * 4 + k]).sum();
+            }
+        }
+        Self(ret)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum ScreenCoord {
     Front(vec3_t),
     Back(vec3_t),
 }
 
+fn vec3_ptr(v: &vec3_t) -> *const f32 {
+    AsRef::<[f32; 3]>::as_ref(v).as_ptr()
+}
+
+fn vec3_mut_ptr(v: &mut vec3_t) -> *mut f32 {
+    AsMut::<[f32; 3]>::as_mut(v).as_mut_ptr()
+}
+
 macro_rules! unwrap {
     ($self:expr, $name:ident) => {
         match $self.raw.$name {
@@ -51,7 +250,85 @@ macro_rules! unwrap {
     };
 }
 
-static mut DRAW_LOCK: bool = false;
+static DRAW_LOCK: AtomicBool = AtomicBool::new(false);
+
+static SCISSOR: Sync<Cell<Option<Rect>>> = unsafe { Sync::new(Cell::new(None)) };
+
+static FOG_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The error type which is returned if a draw stream is already open.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DrawError;
+
+impl fmt::Display for DrawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "multiple draw streams".fmt(f)
+    }
+}
+
+/// The error type which is returned by [TriangleApi::set_sprite_frame].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpriteError {
+    /// The frame is outside of the sprite frame range.
+    InvalidFrame { frame: i32, frames: i32 },
+    /// The engine failed to bind the sprite texture.
+    Texture,
+}
+
+impl fmt::Display for SpriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFrame { frame, frames } => {
+                write!(f, "sprite frame {frame} is out of range 0..{frames}")
+            }
+            Self::Texture => "failed to set sprite texture".fmt(f),
+        }
+    }
+}
+
+/// Fog settings for [TriangleApi::set_fog].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Fog {
+    /// Fog color with components in range `0.0..=255.0`, the engine scales it by `1/255`.
+    pub color: [f32; 3],
+    pub start: f32,
+    pub end: f32,
+    pub density: f32,
+    /// Apply the fog to the skybox.
+    pub skybox: bool,
+}
+
+impl Fog {
+    /// Creates a linear fog between `start` and `end` distances.
+    pub const fn linear(color: [f32; 3], start: f32, end: f32) -> Self {
+        Self {
+            color,
+            start,
+            end,
+            density: 0.0,
+            skybox: false,
+        }
+    }
+
+    /// Creates an exponential fog with the given density.
+    ///
+    /// The distances are not used by exponential fog, but the engine disables fog if `end` is
+    /// not greater than `start`, so a valid range is set.
+    pub const fn exponential(color: [f32; 3], density: f32) -> Self {
+        Self {
+            color,
+            start: 0.0,
+            end: 1.0,
+            density,
+            skybox: false,
+        }
+    }
+
+    pub const fn with_skybox(mut self, skybox: bool) -> Self {
+        self.skybox = skybox;
+        self
+    }
+}
 
 pub struct Draw<'a> {
     tri: &'a TriangleApi,
@@ -65,13 +342,23 @@ impl<'a> Draw<'a> {
         Self { tri }
     }
 
+    /// Begins a draw stream or returns an error if another stream is open.
+    pub fn try_begin(tri: &'a TriangleApi, primitive: Primitive) -> Result<Self, DrawError> {
+        DRAW_LOCK
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .map_err(|_| DrawError)?;
+        Ok(unsafe { Self::begin_unchecked(tri, primitive) })
+    }
+
+    /// Begins a draw stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another stream is open.
     pub fn begin(tri: &'a TriangleApi, primitive: Primitive) -> Self {
-        unsafe {
-            if DRAW_LOCK {
-                panic!("multiple draw streams");
-            }
-            DRAW_LOCK = true;
-            Self::begin_unchecked(tri, primitive)
+        match Self::try_begin(tri, primitive) {
+            Ok(draw) => draw,
+            Err(err) => panic!("{err}"),
         }
     }
 
@@ -89,6 +376,10 @@ impl<'a> Draw<'a> {
     //     mem::forget(self);
     // }
 
+    /// Ends the draw stream.
+    ///
+    /// The stream is submitted to the engine when this method returns. Dropping the `Draw`
+    /// has the same effect.
     pub fn end(self) {
         // drop will do the work
     }
@@ -98,6 +389,12 @@ impl<'a> Draw<'a> {
         self
     }
 
+    /// See [TriangleApi::set_color4f_rendermode].
+    pub fn color4f_rendermode(self, r: f32, g: f32, b: f32, a: f32, mode: RenderMode) -> Self {
+        self.tri.set_color4f_rendermode(r, g, b, a, mode);
+        self
+    }
+
     pub fn color4ub(self, r: u8, g: u8, b: u8, a: u8) -> Self {
         self.tri.set_color4ub(r, g, b, a);
         self
@@ -108,6 +405,7 @@ impl<'a> Draw<'a> {
         self
     }
 
+    /// See [TriangleApi::set_brightness].
     pub fn brightness(self, brightness: f32) -> Self {
         self.tri.set_brightness(brightness);
         self
@@ -122,7 +420,7 @@ impl<'a> Draw<'a> {
 
     pub fn vertex3fv(self, pos: vec3_t) -> Self {
         unsafe {
-            unwrap!(self.tri, Vertex3fv)(pos.as_ref().as_ptr());
+            unwrap!(self.tri, Vertex3fv)(vec3_ptr(&pos));
         }
         self
     }
@@ -134,21 +432,41 @@ impl<'a> Draw<'a> {
         self
     }
 
-    // TODO: do we need Color4fRendermode?
-    // pub Color4fRendermode: Option<
-    //     unsafe extern "C" fn(r: f32, g: f32, b: f32, a: f32, rendermode: ::core::ffi::c_int),
-    // >,
+    /// Emits a texture coordinate and a position for each item.
+    pub fn vertices(self, iter: impl IntoIterator<Item = (vec3_t, (f32, f32))>) -> Self {
+        iter.into_iter().fold(self, |draw, (pos, (u, v))| {
+            draw.tex_coord2f(u, v).vertex3fv(pos)
+        })
+    }
+
+    /// Emits a position for each item.
+    pub fn positions(self, iter: impl IntoIterator<Item = vec3_t>) -> Self {
+        iter.into_iter().fold(self, |draw, pos| draw.vertex3fv(pos))
+    }
 }
 
 impl Drop for Draw<'_> {
     fn drop(&mut self) {
+        struct Unlock;
+
+        impl Drop for Unlock {
+            fn drop(&mut self) {
+                DRAW_LOCK.store(false, Ordering::Release);
+            }
+        }
+
+        // hold the lock until the engine is done with the stream, release on unwind too
+        let _unlock = Unlock;
         unsafe {
             self.end_unchecked_impl();
-            DRAW_LOCK = false;
         }
     }
 }
 
+/// Safe wrapper for the engine triangle API.
+///
+/// The engine API has no entries for depth range, polygon offset or scissor. Overlays that
+/// z-fight with world geometry must be offset along the surface normal by the caller.
 #[repr(transparent)]
 pub struct TriangleApi {
     raw: triangleapi_s,
@@ -181,6 +499,15 @@ impl TriangleApi {
         unsafe { unwrap!(self, Color4f)(r, g, b, a) }
     }
 
+    /// Sets the current color adjusted for the given render mode.
+    ///
+    /// With [RenderMode::TransAlpha] the alpha is passed through, other modes premultiply the
+    /// color by alpha. The render mode itself is not changed, it must be set with
+    /// [set_render_mode](Self::set_render_mode).
+    pub fn set_color4f_rendermode(&self, r: f32, g: f32, b: f32, a: f32, mode: RenderMode) {
+        unsafe { unwrap!(self, Color4fRendermode)(r, g, b, a, mode.into_raw()) }
+    }
+
     pub fn set_color4ub(&self, r: u8, g: u8, b: u8, a: u8) {
         unsafe { unwrap!(self, Color4ub)(r, g, b, a) }
     }
@@ -190,7 +517,29 @@ impl TriangleApi {
         self.set_color4ub(color.r(), color.g(), color.b(), color.a());
     }
 
+    /// Sets the current color from a packed `0xRRGGBBAA` value.
+    pub fn set_color_u32(&self, packed: u32) {
+        self.set_color(packed);
+    }
+
+    /// Sets the current color from a base color and a separate alpha value.
+    ///
+    /// Useful for fades where the color stays the same and only alpha changes.
+    pub fn set_color_rgb(&self, rgb: RGB, alpha: u8) {
+        self.set_color(rgb.rgba(alpha));
+    }
+
+    /// Sets the brightness clamped to `0.0..=1.0`.
+    ///
+    /// Use [set_brightness_raw](Self::set_brightness_raw) to pass the value unchanged.
     pub fn set_brightness(&self, brightness: f32) {
+        self.set_brightness_raw(brightness.clamp(0.0, 1.0));
+    }
+
+    /// Sets the brightness without clamping.
+    ///
+    /// Values outside of `0.0..=1.0` may produce different results on different renderers.
+    pub fn set_brightness_raw(&self, brightness: f32) {
         unsafe {
             unwrap!(self, Brightness)(brightness);
         }
@@ -207,17 +556,248 @@ impl TriangleApi {
         status != 0
     }
 
+    /// Binds the texture of a sprite frame.
+    ///
+    /// Unlike [set_sprite_texture](Self::set_sprite_texture) the frame is checked against the
+    /// number of frames in the sprite.
+    pub fn set_sprite_frame(&self, model: &model_s, frame: i32) -> Result<(), SpriteError> {
+        let frames = model.numframes;
+        if !(0..frames).contains(&frame) {
+            return Err(SpriteError::InvalidFrame { frame, frames });
+        }
+        if self.set_sprite_texture(model, frame) {
+            Ok(())
+        } else {
+            Err(SpriteError::Texture)
+        }
+    }
+
     pub fn begin(&self, primitive: Primitive) -> Draw<'_> {
         Draw::begin(self, primitive)
     }
 
-    pub fn world_to_screen(&self, world: vec3_t) -> ScreenCoord {
-        let mut ret = MaybeUninit::<[f32; 3]>::uninit();
-        let behind = unsafe {
-            unwrap!(self, WorldToScreen)(world.as_ref().as_ptr(), ret.as_mut_ptr().cast())
+    /// Begins a draw stream or returns an error if another stream is open.
+    pub fn try_begin(&self, primitive: Primitive) -> Result<Draw<'_>, DrawError> {
+        Draw::try_begin(self, primitive)
+    }
+
+    /// Binds the sprite frame texture and begins a draw stream.
+    ///
+    /// Returns `None` if the sprite texture could not be bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another stream is open. The texture is not bound in this case.
+    pub fn begin_textured(
+        &self,
+        primitive: Primitive,
+        model: &model_s,
+        frame: i32,
+    ) -> Option<Draw<'_>> {
+        if self.is_drawing() {
+            panic!("{DrawError}");
+        }
+        if self.set_sprite_texture(model, frame) {
+            Some(self.begin(primitive))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if a draw stream is open.
+    pub fn is_drawing(&self) -> bool {
+        DRAW_LOCK.load(Ordering::Acquire)
+    }
+
+    /// Asserts that no draw stream is open.
+    ///
+    /// Only checked in debug builds.
+    #[track_caller]
+    pub fn assert_not_drawing(&self) {
+        debug_assert!(!self.is_drawing(), "draw stream is open");
+    }
+
+    /// Draws a quad textured with a sub-rectangle of the sprite frame.
+    ///
+    /// Each corner is paired with the texture coordinate at the same index. Corners must be
+    /// ordered around the quad, for example top-left, top-right, bottom-right and bottom-left
+    /// with UVs `(0, 0)`, `(1, 0)`, `(1, 1)` and `(0, 1)` for the whole frame.
+    ///
+    /// Returns `false` and draws nothing if the sprite texture could not be bound.
+    pub fn draw_textured_quad(
+        &self,
+        model: &model_s,
+        frame: i32,
+        corners: [vec3_t; 4],
+        uvs: [(f32, f32); 4],
+        color: impl Into<RGBA>,
+    ) -> bool {
+        let Some(draw) = self.begin_textured(Primitive::Quads, model, frame) else {
+            return false;
+        };
+        draw.color(color)
+            .vertices(corners.into_iter().zip(uvs))
+            .end();
+        true
+    }
+
+    /// Draws a single line segment.
+    pub fn draw_line(&self, a: vec3_t, b: vec3_t, color: impl Into<RGBA>) {
+        self.begin(Primitive::Lines)
+            .color(color)
+            .vertex3fv(a)
+            .vertex3fv(b)
+            .end();
+    }
+
+    /// Draws a quad with a color per corner.
+    ///
+    /// Each corner uses the color at the same index. Corners must be ordered around the quad,
+    /// for example top-left, top-right, bottom-right and bottom-left. Use the same color for
+    /// both top corners and for both bottom corners to get a vertical gradient.
+    pub fn draw_gradient_quad(&self, corners: [vec3_t; 4], colors: [RGBA; 4]) {
+        let mut draw = self.begin(Primitive::Quads);
+        for (pos, color) in corners.into_iter().zip(colors) {
+            draw = draw.color(color).vertex3fv(pos);
+        }
+        draw.end();
+    }
+
+    /// Draws the edges of the current view frustum.
+    ///
+    /// The corners are reconstructed from the inverse of the projection and modelview
+    /// matrices. Nothing is drawn if the combined matrix is singular.
+    pub fn draw_frustum(&self, color: impl Into<RGBA>) {
+        let modelview = self.get_matrix(MatrixKind::ModelView);
+        let projection = self.get_matrix(MatrixKind::Projection);
+        let Some(inv) = (projection * modelview).inverse() else {
+            return;
         };
-        // SAFETY: the engine method initialize the value
-        let ret = unsafe { ret.assume_init().into() };
+
+        let corners: [vec3_t; 8] = core::array::from_fn(|i| {
+            let x = if i & 1 != 0 { 1.0 } else { -1.0 };
+            let y = if i & 2 != 0 { 1.0 } else { -1.0 };
+            let z = if i & 4 != 0 { 1.0 } else { -1.0 };
+            inv.transform_point(vec3_t::new(x, y, z))
+        });
+        self.draw_box_edges(&corners, color);
+    }
+
+    /// Draws the edges of an axis-aligned box.
+    pub fn draw_wire_box(&self, mins: vec3_t, maxs: vec3_t, color: impl Into<RGBA>) {
+        let corners: [vec3_t; 8] = core::array::from_fn(|i| {
+            vec3_t::new(
+                if i & 1 != 0 { maxs.x } else { mins.x },
+                if i & 2 != 0 { maxs.y } else { mins.y },
+                if i & 4 != 0 { maxs.z } else { mins.z },
+            )
+        });
+        self.draw_box_edges(&corners, color);
+    }
+
+    /// Draws 12 edges between box corners.
+    ///
+    /// Bits 0, 1 and 2 of a corner index select the max side on the X, Y and Z axes.
+    fn draw_box_edges(&self, corners: &[vec3_t; 8], color: impl Into<RGBA>) {
+        const EDGES: [(usize, usize); 12] = [
+            // bottom
+            (0, 1),
+            (1, 3),
+            (3, 2),
+            (2, 0),
+            // top
+            (4, 5),
+            (5, 7),
+            (7, 6),
+            (6, 4),
+            // sides
+            (0, 4),
+            (1, 5),
+            (2, 6),
+            (3, 7),
+        ];
+
+        let mut draw = self.begin(Primitive::Lines).color(color);
+        for (a, b) in EDGES {
+            draw = draw.vertex3fv(corners[a]).vertex3fv(corners[b]);
+        }
+        draw.end();
+    }
+
+    /// Draws a filled quad.
+    ///
+    /// Vertices must be ordered around the quad.
+    pub fn draw_quad(&self, verts: [vec3_t; 4], color: impl Into<RGBA>) {
+        self.begin(Primitive::Quads)
+            .color(color)
+            .positions(verts)
+            .end();
+    }
+
+    /// Draws all vertices in a single stream.
+    ///
+    /// Color, texture coordinates and position are emitted for each vertex in that order.
+    ///
+    /// Returns `false` and draws nothing if the vertex count is not valid for the primitive.
+    pub fn draw_mesh(&self, primitive: Primitive, verts: &[Vertex]) -> bool {
+        if !primitive.is_valid_vertex_count(verts.len()) {
+            return false;
+        }
+        let mut draw = self.begin(primitive);
+        for v in verts {
+            draw = draw
+                .color(v.color)
+                .tex_coord2f(v.uv.0, v.uv.1)
+                .vertex3fv(v.pos);
+        }
+        draw.end();
+        true
+    }
+
+    /// Sets a clip rectangle in screen coordinates for the 2D helpers.
+    ///
+    /// The engine does not expose a hardware scissor, so clipping is done in software by
+    /// [draw_textured_rect](Self::draw_textured_rect). Other draw calls are not clipped.
+    pub fn set_scissor(&self, x: i32, y: i32, width: u32, height: u32) {
+        SCISSOR.set(Some(Rect::new(x, y, width, height)));
+    }
+
+    /// Removes the clip rectangle set by [set_scissor](Self::set_scissor).
+    pub fn clear_scissor(&self) {
+        SCISSOR.set(None);
+    }
+
+    /// Returns the current clip rectangle.
+    ///
+    /// Can be used to save and restore the clip state around nested panels.
+    pub fn scissor(&self) -> Option<Rect> {
+        SCISSOR.get()
+    }
+
+    /// Draws the whole sprite frame into a rectangle in screen coordinates.
+    ///
+    /// The rectangle and its texture coordinates are clipped to the
+    /// [scissor](Self::set_scissor) if it is set.
+    ///
+    /// Returns `false` and draws nothing if the sprite texture could not be bound. Returns
+    /// `true` without drawing if the rectangle is empty or fully clipped.
+    pub fn draw_textured_rect(
+        &self,
+        model: &model_s,
+        frame: i32,
+        rect: Rect,
+        color: impl Into<RGBA>,
+    ) -> bool {
+        let Some((corners, uvs)) = clip_textured_rect(rect, self.scissor()) else {
+            return true;
+        };
+        self.draw_textured_quad(model, frame, corners, uvs, color)
+    }
+
+    pub fn world_to_screen(&self, world: vec3_t) -> ScreenCoord {
+        let mut ret = vec3_t::ZERO;
+        let behind =
+            unsafe { unwrap!(self, WorldToScreen)(vec3_ptr(&world), vec3_mut_ptr(&mut ret)) };
         if behind == 0 {
             ScreenCoord::Front(ret)
         } else {
@@ -225,13 +805,57 @@ impl TriangleApi {
         }
     }
 
+    /// Projects a world position to pixel coordinates on a screen of the given size.
+    ///
+    /// Returns `None` if the point is behind the camera. The engine reports points at or
+    /// behind the near plane as behind.
+    ///
+    /// The origin is the top-left corner of the screen and Y grows downwards.
+    pub fn world_to_screen_px(
+        &self,
+        world: vec3_t,
+        screen_w: u32,
+        screen_h: u32,
+    ) -> Option<(f32, f32)> {
+        match self.world_to_screen(world) {
+            ScreenCoord::Front(ndc) => {
+                let x = (ndc.x * 0.5 + 0.5) * screen_w as f32;
+                let y = (0.5 - ndc.y * 0.5) * screen_h as f32;
+                Some((x, y))
+            }
+            ScreenCoord::Back(_) => None,
+        }
+    }
+
     pub fn screen_to_world(&self, screen: vec3_t) -> vec3_t {
-        let mut ret = MaybeUninit::<[f32; 3]>::uninit();
+        let mut ret = vec3_t::ZERO;
         unsafe {
-            unwrap!(self, ScreenToWorld)(screen.as_ref().as_ptr(), ret.as_mut_ptr().cast());
+            unwrap!(self, ScreenToWorld)(vec3_ptr(&screen), vec3_mut_ptr(&mut ret));
         }
-        // SAFETY: the engine method initialize the value
-        unsafe { ret.assume_init().into() }
+        ret
+    }
+
+    /// Returns a pick ray through the given pixel as the camera origin and a normalized
+    /// direction.
+    ///
+    /// The pixel coordinates are relative to a screen of the given size. The origin is the
+    /// top-left corner of the screen and Y grows downwards, like in
+    /// [world_to_screen_px](Self::world_to_screen_px).
+    ///
+    /// The ray is computed from the current projection and modelview matrices, so it follows
+    /// the active FOV. Returns `None` if the matrices are singular.
+    pub fn screen_ray(
+        &self,
+        x: f32,
+        y: f32,
+        screen_w: u32,
+        screen_h: u32,
+    ) -> Option<(vec3_t, vec3_t)> {
+        let modelview = self.get_matrix(MatrixKind::ModelView);
+        let projection = self.get_matrix(MatrixKind::Projection);
+        let ndc_x = x / screen_w as f32 * 2.0 - 1.0;
+        let ndc_y = 1.0 - y / screen_h as f32 * 2.0;
+        pick_ray(&projection, &modelview, ndc_x, ndc_y)
     }
 
     pub fn get_matrix_raw(&self, param_name: i32) -> [f32; 16] {
@@ -243,29 +867,65 @@ impl TriangleApi {
         ret
     }
 
+    /// Returns the current OpenGL matrix.
+    pub fn get_matrix(&self, kind: MatrixKind) -> Matrix4 {
+        Matrix4::from_cols_array(self.get_matrix_raw(kind.into_raw()))
+    }
+
     pub fn is_box_in_pvs(&self, mins: vec3_t, maxs: vec3_t) -> bool {
-        let mins = mins.as_ref().as_ptr();
-        let maxs = maxs.as_ref().as_ptr();
+        let mins = vec3_ptr(&mins).cast_mut();
+        let maxs = vec3_ptr(&maxs).cast_mut();
         // FIXME: ffi: why mins and maxs are mutable?
-        unsafe { unwrap!(self, BoxInPVS)(mins.cast_mut(), maxs.cast_mut()) != 0 }
+        unsafe { unwrap!(self, BoxInPVS)(mins, maxs) != 0 }
+    }
+
+    /// Returns `true` if the model bounds placed at `origin` are in the PVS.
+    ///
+    /// The model bounds are local to the model and are offset by `origin`.
+    pub fn is_model_in_pvs(&self, model: &model_s, origin: vec3_t) -> bool {
+        self.is_box_in_pvs(origin + model.mins, origin + model.maxs)
     }
 
     pub fn light_at_point(&self, pos: vec3_t) -> [f32; 3] {
-        let pos = pos.as_ref().as_ptr();
-        let mut color = MaybeUninit::<[f32; 3]>::uninit();
+        let mut color = [0.0; 3];
         unsafe {
             // FIXME: ffi: why pos is mutable?
-            unwrap!(self, LightAtPoint)(pos.cast_mut(), color.as_mut_ptr().cast());
+            unwrap!(self, LightAtPoint)(vec3_ptr(&pos).cast_mut(), color.as_mut_ptr());
         }
-        // SAFETY: the engine method initialize the value
-        unsafe { color.assume_init() }
+        color
+    }
+
+    /// Returns the average light color of sample points inside the box.
+    ///
+    /// Points are spread over the box with a fixed Halton sequence, so the result is stable
+    /// between frames for the same box. With one sample (or zero) only the box center is
+    /// sampled.
+    pub fn light_at_box(&self, mins: vec3_t, maxs: vec3_t, samples: u32) -> [f32; 3] {
+        if samples <= 1 {
+            return self.light_at_point((mins + maxs) * 0.5);
+        }
+        let size = maxs - mins;
+        let mut sum = [0.0; 3];
+        for i in 1..=samples {
+            let t = vec3_t::new(halton(i, 2), halton(i, 3), halton(i, 5));
+            let color = self.light_at_point(mins + size * t);
+            for (s, c) in sum.iter_mut().zip(color) {
+                *s += c;
+            }
+        }
+        sum.map(|s| s / samples as f32)
     }
 
     pub fn fog_params(&self, density: f32, skybox: bool) {
         unsafe { unwrap!(self, FogParams)(density, skybox as c_int) }
     }
 
+    /// Sets fog color and distances.
+    ///
+    /// The color components are in range `0.0..=255.0`. The engine disables fog if `end` is
+    /// not greater than `start`.
     pub fn fog(&self, color: &[f32; 3], start: f32, end: f32, on: bool) {
+        FOG_ENABLED.store(on && end > start, Ordering::Relaxed);
         unsafe {
             unwrap!(self, Fog)(
                 // FIXME: ffi: why color is mutable?
@@ -276,4 +936,248 @@ impl TriangleApi {
             )
         }
     }
+
+    /// Enables fog with the given settings.
+    pub fn set_fog(&self, fog: &Fog) {
+        // the engine resets fog params in Fog, so they must be set after it
+        self.fog(&fog.color, fog.start, fog.end, true);
+        self.fog_params(fog.density, fog.skybox);
+    }
+
+    /// Disables fog.
+    pub fn clear_fog(&self) {
+        self.fog(&[0.0; 3], 0.0, 0.0, false);
+    }
+
+    /// Returns `true` if fog was enabled by the last call to [fog](Self::fog).
+    ///
+    /// Only tracks calls made through this crate, fog set by the engine itself is not
+    /// reflected.
+    pub fn is_fog_enabled(&self) -> bool {
+        FOG_ENABLED.load(Ordering::Relaxed)
+    }
+}
+
+/// Clips a rectangle to an optional clip rectangle and returns the corners with their
+/// texture coordinates.
+///
+/// Returns `None` if nothing is left after clipping.
+fn clip_textured_rect(rect: Rect, clip: Option<Rect>) -> Option<([vec3_t; 4], [(f32, f32); 4])> {
+    if rect.width == 0 || rect.height == 0 {
+        return None;
+    }
+    // do not use Rect::right and Rect::bottom, they panic on large sizes
+    let edges = |r: Rect| {
+        let x = r.x as f32;
+        let y = r.y as f32;
+        (x, y, x + r.width as f32, y + r.height as f32)
+    };
+    let (x0, y0, x1, y1) = edges(rect);
+    let (cx0, cy0, cx1, cy1) = match clip.map(edges) {
+        Some((l, t, r, b)) => (x0.max(l), y0.max(t), x1.min(r), y1.min(b)),
+        None => (x0, y0, x1, y1),
+    };
+    if cx0 >= cx1 || cy0 >= cy1 {
+        return None;
+    }
+    let w = x1 - x0;
+    let h = y1 - y0;
+    let u0 = (cx0 - x0) / w;
+    let v0 = (cy0 - y0) / h;
+    let u1 = (cx1 - x0) / w;
+    let v1 = (cy1 - y0) / h;
+    let corners = [
+        vec3_t::new(cx0, cy0, 0.0),
+        vec3_t::new(cx1, cy0, 0.0),
+        vec3_t::new(cx1, cy1, 0.0),
+        vec3_t::new(cx0, cy1, 0.0),
+    ];
+    let uvs = [(u0, v0), (u1, v0), (u1, v1), (u0, v1)];
+    Some((corners, uvs))
+}
+
+fn pick_ray(projection: &Matrix4, modelview: &Matrix4, x: f32, y: f32) -> Option<(vec3_t, vec3_t)> {
+    let inv_view = modelview.inverse()?;
+    let inv = (*projection * *modelview).inverse()?;
+    let origin = inv_view.transform_point(vec3_t::ZERO);
+    let near = inv.transform_point(vec3_t::new(x, y, -1.0));
+    let far = inv.transform_point(vec3_t::new(x, y, 1.0));
+    let dir = far - near;
+    let len = sqrtf(dir.dot(dir));
+    if len <= 0.0 {
+        return None;
+    }
+    Some((origin, dir / len))
+}
+
+/// Returns an element of the Halton low-discrepancy sequence in range `0.0..1.0`.
+fn halton(mut index: u32, base: u32) -> f32 {
+    let mut f = 1.0;
+    let mut ret = 0.0;
+    while index > 0 {
+        f /= base as f32;
+        ret += f * (index % base) as f32;
+        index /= base;
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::fabsf;
+
+    #[track_caller]
+    fn assert_vec_eq(a: vec3_t, b: vec3_t) {
+        assert!(a.abs_diff_eq(b, 1e-4), "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn draw_lock() {
+        static LOCKED_IN_END: AtomicBool = AtomicBool::new(false);
+
+        unsafe extern "C" fn begin(_: c_int) {}
+        unsafe extern "C" fn end() {
+            LOCKED_IN_END.store(DRAW_LOCK.load(Ordering::Acquire), Ordering::Relaxed);
+        }
+
+        let mut raw: triangleapi_s = unsafe { mem::zeroed() };
+        raw.Begin = Some(begin);
+        raw.End = Some(end);
+        let tri = TriangleApi::new(&raw);
+
+        let draw = tri.begin(Primitive::Lines);
+        assert!(tri.is_drawing());
+        assert_eq!(tri.try_begin(Primitive::Lines).err(), Some(DrawError));
+        draw.end();
+        assert!(!tri.is_drawing());
+        assert!(LOCKED_IN_END.load(Ordering::Relaxed));
+
+        let draw = tri.try_begin(Primitive::Lines);
+        assert!(draw.is_ok());
+        drop(draw);
+        assert!(!tri.is_drawing());
+    }
+
+    #[test]
+    fn clip_textured_rect_uvs() {
+        let rect = Rect::new(0, 0, 100, 50);
+
+        let (corners, uvs) = clip_textured_rect(rect, None).unwrap();
+        assert_eq!(corners[0], vec3_t::new(0.0, 0.0, 0.0));
+        assert_eq!(corners[2], vec3_t::new(100.0, 50.0, 0.0));
+        assert_eq!(uvs, [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+
+        let clip = Rect::new(50, -10, 100, 35);
+        let (corners, uvs) = clip_textured_rect(rect, Some(clip)).unwrap();
+        assert_eq!(corners[0], vec3_t::new(50.0, 0.0, 0.0));
+        assert_eq!(corners[2], vec3_t::new(100.0, 25.0, 0.0));
+        assert_eq!(uvs, [(0.5, 0.0), (1.0, 0.0), (1.0, 0.5), (0.5, 0.5)]);
+
+        let clip = Rect::new(100, 0, 10, 10);
+        assert_eq!(clip_textured_rect(rect, Some(clip)), None);
+        assert_eq!(clip_textured_rect(Rect::new(0, 0, 0, 10), None), None);
+
+        let clip = Rect::new(0, 0, u32::MAX, u32::MAX);
+        let (_, uvs) = clip_textured_rect(rect, Some(clip)).unwrap();
+        assert_eq!(uvs, [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+    }
+
+    #[test]
+    fn halton_sequence() {
+        assert_eq!(halton(1, 2), 0.5);
+        assert_eq!(halton(2, 2), 0.25);
+        assert_eq!(halton(3, 2), 0.75);
+        assert_eq!(halton(1, 3), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn matrix_layout() {
+        let m = Matrix4::from_cols_array(core::array::from_fn(|i| i as f32));
+        assert_eq!(m.get(1, 0), 1.0);
+        assert_eq!(m.get(0, 1), 4.0);
+        assert_eq!(m.row(0), [0.0, 4.0, 8.0, 12.0]);
+        assert_eq!(m.col(3), [12.0, 13.0, 14.0, 15.0]);
+    }
+
+    #[test]
+    fn matrix_transform_point() {
+        let mut m = Matrix4::IDENTITY.to_cols_array();
+        m[12] = 1.0;
+        m[13] = 2.0;
+        m[14] = 3.0;
+        let m = Matrix4::from_cols_array(m);
+        let p = m.transform_point(vec3_t::new(1.0, 1.0, 1.0));
+        assert_vec_eq(p, vec3_t::new(2.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn pick_ray_identity() {
+        let m = Matrix4::IDENTITY;
+        let (origin, dir) = pick_ray(&m, &m, 0.5, -0.5).unwrap();
+        assert_eq!(origin, vec3_t::ZERO);
+        assert_eq!(dir, vec3_t::new(0.0, 0.0, 1.0));
+
+        let singular = Matrix4::from_cols_array([0.0; 16]);
+        assert_eq!(pick_ray(&singular, &m, 0.0, 0.0), None);
+        assert_eq!(pick_ray(&m, &singular, 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn pick_ray_perspective() {
+        // 90 degrees vertical FOV, 4:3 aspect, near 1, far 100
+        let (n, f, aspect) = (1.0, 100.0, 4.0 / 3.0);
+        let sx = 1.0 / aspect;
+        let sz = (f + n) / (n - f);
+        let tz = 2.0 * f * n / (n - f);
+        let projection = Matrix4::from_cols_array([
+            sx, 0.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0, //
+            0.0, 0.0, sz, -1.0, //
+            0.0, 0.0, tz, 0.0, //
+        ]);
+        // camera at (1, 2, 3)
+        let modelview = Matrix4::from_cols_array([
+            1.0, 0.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0, //
+            0.0, 0.0, 1.0, 0.0, //
+            -1.0, -2.0, -3.0, 1.0, //
+        ]);
+
+        let (origin, dir) = pick_ray(&projection, &modelview, 0.0, 0.0).unwrap();
+        assert_vec_eq(origin, vec3_t::new(1.0, 2.0, 3.0));
+        assert_vec_eq(dir, vec3_t::new(0.0, 0.0, -1.0));
+
+        let (_, dir) = pick_ray(&projection, &modelview, 1.0, 1.0).unwrap();
+        let expected = vec3_t::new(aspect, 1.0, -1.0);
+        assert_vec_eq(dir, expected / sqrtf(expected.dot(expected)));
+    }
+
+    #[test]
+    fn matrix_inverse() {
+        let m = Matrix4::from_cols_array([
+            2.0, 0.5, 0.0, 0.1, //
+            1.0, 3.0, 0.2, 0.0, //
+            0.3, 0.0, 4.0, 0.5, //
+            5.0, 6.0, 7.0, 1.0, //
+        ]);
+        let inv = m.inverse().unwrap();
+        for (a, b) in (m * inv).to_cols_array().iter().zip(Matrix4::IDENTITY.0) {
+            assert!(fabsf(a - b) < 1e-4);
+        }
+        let p = vec3_t::new(1.0, -2.0, 3.0);
+        assert_vec_eq(inv.transform_point(m.transform_point(p)), p);
+        assert_eq!(Matrix4::from_cols_array([0.0; 16]).inverse(), None);
+
+        let s = 0.005;
+        let scale = Matrix4::from_cols_array([
+            s, 0.0, 0.0, 0.0, //
+            0.0, s, 0.0, 0.0, //
+            0.0, 0.0, s, 0.0, //
+            0.0, 0.0, 0.0, 1.0, //
+        ]);
+        let inv = scale.inverse().unwrap();
+        let p = inv.transform_point(vec3_t::new(s, s, s));
+        assert_vec_eq(p, vec3_t::new(1.0, 1.0, 1.0));
+    }
 }
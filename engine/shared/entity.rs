@@ -377,22 +377,37 @@ bitflags! {
     #[repr(transparent)]
     pub struct Buttons: i32 {
         const NONE          = 0;
+        /// Primary attack (`+attack`).
         const ATTACK        = ffi::common::IN_ATTACK;
+        /// Jump (`+jump`).
         const JUMP          = ffi::common::IN_JUMP;
+        /// Crouch (`+duck`).
         const DUCK          = ffi::common::IN_DUCK;
+        /// Move forward (`+forward`).
         const FORWARD       = ffi::common::IN_FORWARD;
+        /// Move backward (`+back`).
         const BACK          = ffi::common::IN_BACK;
+        /// Use an object (`+use`).
         const USE           = ffi::common::IN_USE;
+        /// Cancel, set by the client outside of key bindings.
         const CANCEL        = ffi::common::IN_CANCEL;
+        /// Turn left (`+left`).
         const LEFT          = ffi::common::IN_LEFT;
+        /// Turn right (`+right`).
         const RIGHT         = ffi::common::IN_RIGHT;
+        /// Strafe left (`+moveleft`).
         const MOVELEFT      = ffi::common::IN_MOVELEFT;
+        /// Strafe right (`+moveright`).
         const MOVERIGHT     = ffi::common::IN_MOVERIGHT;
+        /// Secondary attack (`+attack2`).
         const ATTACK2       = ffi::common::IN_ATTACK2;
+        /// Walk or run modifier (`+speed`).
         const RUN           = ffi::common::IN_RUN;
+        /// Reload (`+reload`).
         const RELOAD        = ffi::common::IN_RELOAD;
+        /// Alternative action (`+alt1`).
         const ALT1          = ffi::common::IN_ALT1;
-        // Used by client for when scoreboard is held down.
+        /// Used by client for when scoreboard is held down.
         const SCORE         = ffi::common::IN_SCORE;
     }
 }
@@ -429,4 +444,14 @@ impl Buttons {
         fn is_alt1          = ALT1,
         fn is_score         = SCORE,
     }
+
+    /// Returns buttons that are pressed in `cur` but were not pressed in `prev`.
+    pub const fn pressed(prev: Self, cur: Self) -> Self {
+        cur.difference(prev)
+    }
+
+    /// Returns buttons that were pressed in `prev` but are not pressed in `cur`.
+    pub const fn released(prev: Self, cur: Self) -> Self {
+        prev.difference(cur)
+    }
 }
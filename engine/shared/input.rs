@@ -1,5 +1,10 @@
+use alloc::{collections::VecDeque, vec::Vec};
+use core::fmt;
+
 use bitflags::bitflags;
 
+use crate::{entity::Buttons, ffi::keys};
+
 bitflags! {
     #[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
     pub struct KeyState: i32 {
@@ -11,3 +16,494 @@ bitflags! {
         const ANY_DOWN      = Self::DOWN.union(Self::IMPULSE_DOWN).bits();
     }
 }
+
+macro_rules! define_keys {
+    ($($name:ident = $code:expr => $str:literal),* $(,)?) => {
+        /// Engine key codes.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum Key {
+            $($name,)*
+        }
+
+        impl Key {
+            /// All known keys.
+            pub const ALL: &[Key] = &[$(Self::$name,)*];
+
+            /// Returns the engine key code.
+            pub const fn code(self) -> i32 {
+                match self {
+                    $(Self::$name => $code as i32,)*
+                }
+            }
+
+            /// Returns the key name used by the engine in bindings.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Self::$name => $str,)*
+                }
+            }
+        }
+    };
+}
+
+define_keys! {
+    Tab = keys::K_TAB => "TAB",
+    Enter = keys::K_ENTER => "ENTER",
+    Escape = keys::K_ESCAPE => "ESCAPE",
+    Space = keys::K_SPACE => "SPACE",
+    Backspace = keys::K_BACKSPACE => "BACKSPACE",
+    UpArrow = keys::K_UPARROW => "UPARROW",
+    DownArrow = keys::K_DOWNARROW => "DOWNARROW",
+    LeftArrow = keys::K_LEFTARROW => "LEFTARROW",
+    RightArrow = keys::K_RIGHTARROW => "RIGHTARROW",
+    Alt = keys::K_ALT => "ALT",
+    Ctrl = keys::K_CTRL => "CTRL",
+    Shift = keys::K_SHIFT => "SHIFT",
+    F1 = keys::K_F1 => "F1",
+    F2 = keys::K_F2 => "F2",
+    F3 = keys::K_F3 => "F3",
+    F4 = keys::K_F4 => "F4",
+    F5 = keys::K_F5 => "F5",
+    F6 = keys::K_F6 => "F6",
+    F7 = keys::K_F7 => "F7",
+    F8 = keys::K_F8 => "F8",
+    F9 = keys::K_F9 => "F9",
+    F10 = keys::K_F10 => "F10",
+    F11 = keys::K_F11 => "F11",
+    F12 = keys::K_F12 => "F12",
+    Ins = keys::K_INS => "INS",
+    Del = keys::K_DEL => "DEL",
+    PgDn = keys::K_PGDN => "PGDN",
+    PgUp = keys::K_PGUP => "PGUP",
+    Home = keys::K_HOME => "HOME",
+    End = keys::K_END => "END",
+    CapsLock = keys::K_CAPSLOCK => "CAPSLOCK",
+    ScrollLock = keys::K_SCROLLLOCK => "SCROLLLOCK",
+    Win = keys::K_WIN => "WIN",
+    Pause = keys::K_PAUSE => "PAUSE",
+    MWheelDown = keys::K_MWHEELDOWN => "MWHEELDOWN",
+    MWheelUp = keys::K_MWHEELUP => "MWHEELUP",
+    Mouse1 = keys::K_MOUSE1 => "MOUSE1",
+    Mouse2 = keys::K_MOUSE2 => "MOUSE2",
+    Mouse3 = keys::K_MOUSE3 => "MOUSE3",
+    Mouse4 = keys::K_MOUSE4 => "MOUSE4",
+    Mouse5 = keys::K_MOUSE5 => "MOUSE5",
+
+    KpHome = keys::K_KP_HOME => "KP_HOME",
+    KpUpArrow = keys::K_KP_UPARROW => "KP_UPARROW",
+    KpPgUp = keys::K_KP_PGUP => "KP_PGUP",
+    KpLeftArrow = keys::K_KP_LEFTARROW => "KP_LEFTARROW",
+    Kp5 = keys::K_KP_5 => "KP_5",
+    KpRightArrow = keys::K_KP_RIGHTARROW => "KP_RIGHTARROW",
+    KpEnd = keys::K_KP_END => "KP_END",
+    KpDownArrow = keys::K_KP_DOWNARROW => "KP_DOWNARROW",
+    KpPgDn = keys::K_KP_PGDN => "KP_PGDN",
+    KpEnter = keys::K_KP_ENTER => "KP_ENTER",
+    KpIns = keys::K_KP_INS => "KP_INS",
+    KpDel = keys::K_KP_DEL => "KP_DEL",
+    KpSlash = keys::K_KP_SLASH => "KP_SLASH",
+    KpMinus = keys::K_KP_MINUS => "KP_MINUS",
+    KpPlus = keys::K_KP_PLUS => "KP_PLUS",
+
+    Backquote = b'`' => "`",
+    Minus = b'-' => "-",
+    Equals = b'=' => "=",
+    LeftBracket = b'[' => "[",
+    RightBracket = b']' => "]",
+    Backslash = b'\\' => "\\",
+    Semicolon = b';' => "SEMICOLON",
+    Apostrophe = b'\'' => "'",
+    Comma = b',' => ",",
+    Period = b'.' => ".",
+    Slash = b'/' => "/",
+
+    Digit0 = b'0' => "0",
+    Digit1 = b'1' => "1",
+    Digit2 = b'2' => "2",
+    Digit3 = b'3' => "3",
+    Digit4 = b'4' => "4",
+    Digit5 = b'5' => "5",
+    Digit6 = b'6' => "6",
+    Digit7 = b'7' => "7",
+    Digit8 = b'8' => "8",
+    Digit9 = b'9' => "9",
+
+    A = b'a' => "a",
+    B = b'b' => "b",
+    C = b'c' => "c",
+    D = b'd' => "d",
+    E = b'e' => "e",
+    F = b'f' => "f",
+    G = b'g' => "g",
+    H = b'h' => "h",
+    I = b'i' => "i",
+    J = b'j' => "j",
+    K = b'k' => "k",
+    L = b'l' => "l",
+    M = b'm' => "m",
+    N = b'n' => "n",
+    O = b'o' => "o",
+    P = b'p' => "p",
+    Q = b'q' => "q",
+    R = b'r' => "r",
+    S = b's' => "s",
+    T = b't' => "t",
+    U = b'u' => "u",
+    V = b'v' => "v",
+    W = b'w' => "w",
+    X = b'x' => "x",
+    Y = b'y' => "y",
+    Z = b'z' => "z",
+}
+
+impl Key {
+    /// Creates a key from the engine key code.
+    pub fn from_code(code: i32) -> Option<Key> {
+        Self::ALL.iter().copied().find(|key| key.code() == code)
+    }
+
+    /// Creates a key from the engine key name, ignoring ASCII case.
+    pub fn from_name(name: &str) -> Option<Key> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|key| key.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+/// A key press or release transition.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeyEvent {
+    Pressed(Key),
+    Released(Key),
+}
+
+/// Turns key states into a queue of press and release events.
+///
+/// Call [update](Self::update) once per frame for each key. The update clears impulse bits
+/// of the key in place, so every transition is queued once. The previous and current states
+/// of each key are stored.
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    keys: Vec<(Key, KeyState, KeyState)>,
+    events: VecDeque<KeyEvent>,
+}
+
+impl InputState {
+    pub const fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Returns the state of the key passed to the last update.
+    pub fn state(&self, key: Key) -> KeyState {
+        self.keys
+            .iter()
+            .find(|(k, ..)| *k == key)
+            .map_or(KeyState::NONE, |(_, _, cur)| *cur)
+    }
+
+    /// Returns the state of the key passed to the update before the last one.
+    pub fn previous_state(&self, key: Key) -> KeyState {
+        self.keys
+            .iter()
+            .find(|(k, ..)| *k == key)
+            .map_or(KeyState::NONE, |(_, prev, _)| *prev)
+    }
+
+    /// Queues transitions for the key and clears impulse bits of the state.
+    ///
+    /// Transitions are taken from the impulse bits. If none are set, a change of the down
+    /// bit since the previous update is queued instead.
+    pub fn update(&mut self, key: Key, state: &mut KeyState) {
+        let prev = self.state(key);
+        let down = state.contains(KeyState::DOWN);
+        let impulse_down = state.contains(KeyState::IMPULSE_DOWN);
+        let impulse_up = state.contains(KeyState::IMPULSE_UP);
+
+        match (impulse_down, impulse_up) {
+            (true, true) if down => {
+                // released and re-pressed this frame
+                self.events.push_back(KeyEvent::Released(key));
+                self.events.push_back(KeyEvent::Pressed(key));
+            }
+            (true, true) => {
+                // pressed and released this frame
+                self.events.push_back(KeyEvent::Pressed(key));
+                self.events.push_back(KeyEvent::Released(key));
+            }
+            (true, false) => self.events.push_back(KeyEvent::Pressed(key)),
+            (false, true) => self.events.push_back(KeyEvent::Released(key)),
+            (false, false) => {
+                if down && !prev.contains(KeyState::DOWN) {
+                    self.events.push_back(KeyEvent::Pressed(key));
+                } else if !down && prev.contains(KeyState::DOWN) {
+                    self.events.push_back(KeyEvent::Released(key));
+                }
+            }
+        }
+
+        *state &= KeyState::DOWN;
+        match self.keys.iter_mut().find(|(k, ..)| *k == key) {
+            Some((_, p, cur)) => {
+                *p = *cur;
+                *cur = *state;
+            }
+            None => self.keys.push((key, KeyState::NONE, *state)),
+        }
+    }
+
+    /// Removes and returns all queued events in the order they happened.
+    pub fn drain(&mut self) -> impl Iterator<Item = KeyEvent> + '_ {
+        self.events.drain(..)
+    }
+}
+
+/// Analog input axes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    MoveForward,
+    MoveSide,
+    LookX,
+    LookY,
+    TriggerLeft,
+    TriggerRight,
+}
+
+impl Axis {
+    /// Number of axes.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// All axes.
+    pub const ALL: [Axis; 6] = [
+        Self::MoveForward,
+        Self::MoveSide,
+        Self::LookX,
+        Self::LookY,
+        Self::TriggerLeft,
+        Self::TriggerRight,
+    ];
+}
+
+/// The state of an analog axis.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AnalogAxis {
+    /// Normalized value in range `-1.0..=1.0`.
+    pub value: f32,
+    /// The value change since the previous update.
+    pub delta: f32,
+}
+
+/// Analog axes with per-axis deadzones.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AxisState {
+    axes: [AnalogAxis; Axis::COUNT],
+    deadzones: [f32; Axis::COUNT],
+}
+
+impl AxisState {
+    pub const fn new() -> Self {
+        Self {
+            axes: [AnalogAxis {
+                value: 0.0,
+                delta: 0.0,
+            }; Axis::COUNT],
+            deadzones: [0.0; Axis::COUNT],
+        }
+    }
+
+    pub fn get(&self, axis: Axis) -> AnalogAxis {
+        self.axes[axis as usize]
+    }
+
+    pub fn deadzone(&self, axis: Axis) -> f32 {
+        self.deadzones[axis as usize]
+    }
+
+    /// Sets the deadzone of the axis, clamped to `0.0..=1.0`.
+    pub fn set_deadzone(&mut self, axis: Axis, deadzone: f32) {
+        self.deadzones[axis as usize] = deadzone.clamp(0.0, 1.0);
+    }
+
+    /// Updates the axis from a raw value and returns the new normalized value.
+    ///
+    /// The raw value is clamped to `-1.0..=1.0`. Values inside the deadzone become zero and
+    /// the rest of the range is rescaled so the output still reaches `-1.0` and `1.0`.
+    pub fn set(&mut self, axis: Axis, raw: f32) -> f32 {
+        let deadzone = self.deadzone(axis);
+        let raw = raw.clamp(-1.0, 1.0);
+        let value = if raw > deadzone {
+            (raw - deadzone) / (1.0 - deadzone)
+        } else if raw < -deadzone {
+            (raw + deadzone) / (1.0 - deadzone)
+        } else {
+            0.0
+        };
+        let state = &mut self.axes[axis as usize];
+        state.delta = value - state.value;
+        state.value = value;
+        value
+    }
+}
+
+/// Builds the usercmd button mask from key states.
+///
+/// # Examples
+///
+/// ```
+/// use xash3d_shared::{entity::Buttons, input::{ButtonsBuilder, KeyState}};
+///
+/// let buttons = ButtonsBuilder::new()
+///     .key(Buttons::FORWARD, KeyState::DOWN)
+///     .key(Buttons::JUMP, KeyState::NONE)
+///     .build();
+/// assert_eq!(buttons, Buttons::FORWARD);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ButtonsBuilder {
+    buttons: Buttons,
+}
+
+impl ButtonsBuilder {
+    pub const fn new() -> Self {
+        Self {
+            buttons: Buttons::NONE,
+        }
+    }
+
+    /// Sets `button` if the key is down or was pressed this frame.
+    pub fn key(mut self, button: Buttons, state: KeyState) -> Self {
+        if state.intersects(KeyState::ANY_DOWN) {
+            self.buttons |= button;
+        }
+        self
+    }
+
+    /// Sets `button` if `set` is `true`.
+    pub fn set(mut self, button: Buttons, set: bool) -> Self {
+        if set {
+            self.buttons |= button;
+        }
+        self
+    }
+
+    pub const fn build(self) -> Buttons {
+        self.buttons
+    }
+}
+
+/// A set of keys that must be held down together, like crouch and jump.
+///
+/// Keys are stored as indices into the slice of key states passed to
+/// [all_down](Self::all_down).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeyChord<const N: usize> {
+    keys: [usize; N],
+}
+
+impl<const N: usize> KeyChord<N> {
+    pub const fn new(keys: [usize; N]) -> Self {
+        Self { keys }
+    }
+
+    pub const fn keys(&self) -> &[usize; N] {
+        &self.keys
+    }
+
+    /// Returns `true` if every key in the chord is down or was pressed this frame.
+    ///
+    /// Returns `false` if a key index is out of bounds of `states`.
+    pub fn all_down(&self, states: &[KeyState]) -> bool {
+        self.keys.iter().all(|&i| {
+            states
+                .get(i)
+                .is_some_and(|state| state.intersects(KeyState::ANY_DOWN))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_round_trip() {
+        for &key in Key::ALL {
+            assert_eq!(Key::from_code(key.code()), Some(key));
+            assert_eq!(Key::from_name(key.name()), Some(key));
+        }
+        assert_eq!(Key::from_name("mouse1"), Some(Key::Mouse1));
+        assert_eq!(Key::from_name("A"), Some(Key::A));
+        assert_eq!(Key::from_code(b'a' as i32), Some(Key::A));
+        assert_eq!(Key::from_code(b'-' as i32), Some(Key::Minus));
+        assert_eq!(Key::from_code(b';' as i32), Some(Key::Semicolon));
+        assert_eq!(Key::from_name("semicolon"), Some(Key::Semicolon));
+        assert_eq!(Key::from_name("`"), Some(Key::Backquote));
+        assert_eq!(Key::from_name("\\"), Some(Key::Backslash));
+        assert_eq!(Key::from_name("kp_enter"), Some(Key::KpEnter));
+        assert_eq!(Key::from_code(keys::K_KP_5 as i32), Some(Key::Kp5));
+        assert_eq!(Key::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn input_state_events() {
+        let mut input = InputState::new();
+        let mut space = KeyState::DOWN | KeyState::IMPULSE_DOWN;
+        input.update(Key::Space, &mut space);
+        assert_eq!(space, KeyState::DOWN);
+        input.update(Key::Space, &mut space);
+        assert_eq!(space, KeyState::DOWN);
+        let mut enter = KeyState::IMPULSE_DOWN | KeyState::IMPULSE_UP;
+        input.update(Key::Enter, &mut enter);
+        assert_eq!(enter, KeyState::NONE);
+        input.update(Key::Enter, &mut enter);
+        space = KeyState::IMPULSE_UP;
+        input.update(Key::Space, &mut space);
+        assert_eq!(input.previous_state(Key::Space), KeyState::DOWN);
+
+        let events: Vec<_> = input.drain().collect();
+        assert_eq!(
+            events,
+            [
+                KeyEvent::Pressed(Key::Space),
+                KeyEvent::Pressed(Key::Enter),
+                KeyEvent::Released(Key::Enter),
+                KeyEvent::Released(Key::Space),
+            ]
+        );
+        assert_eq!(input.drain().count(), 0);
+        assert_eq!(input.state(Key::Space), KeyState::NONE);
+    }
+
+    #[test]
+    fn axis_deadzone() {
+        let mut axes = AxisState::new();
+        axes.set_deadzone(Axis::LookX, 0.5);
+        assert_eq!(axes.set(Axis::LookX, 0.1), 0.0);
+        assert_eq!(axes.set(Axis::LookX, -0.5), 0.0);
+        assert_eq!(axes.set(Axis::LookX, 0.75), 0.5);
+        assert_eq!(axes.get(Axis::LookX).delta, 0.5);
+        assert_eq!(axes.set(Axis::LookX, -2.0), -1.0);
+        assert_eq!(axes.set(Axis::MoveSide, 0.1), 0.1);
+    }
+
+    #[test]
+    fn key_chord_all_down() {
+        let chord = KeyChord::new([0, 2]);
+        let up = KeyState::NONE;
+        let down = KeyState::DOWN;
+        let pressed = KeyState::IMPULSE_DOWN;
+        assert!(chord.all_down(&[down, up, down]));
+        assert!(chord.all_down(&[pressed, up, down]));
+        assert!(!chord.all_down(&[down, down, up]));
+        assert!(!chord.all_down(&[down, down]));
+    }
+}
@@ -138,7 +138,7 @@ impl AngleVectors {
         vec3_t::new(
             -self.sr * self.sp * self.cy + -self.cr * -self.sy,
             -self.sr * self.sp * self.sy + -self.cr * self.cy,
-            -self.sr * self.sp,
+            -self.sr * self.cp,
         )
     }
 
@@ -186,6 +186,14 @@ impl AngleVectors {
             up: self.transpose_up(),
         }
     }
+
+    /// Rotates a local vector into the space defined by these angles.
+    ///
+    /// Matches the engine's `AngleMatrix` followed by `VectorRotate`. The local X axis maps
+    /// to forward, Y to left and Z to up.
+    pub fn rotate(&self, v: vec3_t) -> vec3_t {
+        self.forward() * v.x - self.right() * v.y + self.up() * v.z
+    }
 }
 
 pub trait ToAngleVectors {
@@ -198,6 +206,19 @@ impl ToAngleVectors for vec3_t {
     }
 }
 
+pub trait RotateByAngles {
+    /// Rotates this vector by the given angles.
+    ///
+    /// See [AngleVectors::rotate].
+    fn rotate_by_angles(&self, angles: vec3_t) -> Self;
+}
+
+impl RotateByAngles for vec3_t {
+    fn rotate_by_angles(&self, angles: vec3_t) -> Self {
+        angles.angle_vectors().rotate(*self)
+    }
+}
+
 pub fn calc_roll(angles: vec3_t, velocity: vec3_t, roll_angle: f32, roll_speed: f32) -> f32 {
     let right = angles.angle_vectors().right();
     let side = velocity.dot(right);
@@ -260,3 +281,53 @@ pub fn approach_angle(target: f32, value: f32, mut speed: f32) -> f32 {
         target
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn assert_vec_eq(a: vec3_t, b: vec3_t) {
+        assert!(a.abs_diff_eq(b, 1e-5), "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn angle_vectors_identity() {
+        let v = vec3_t::ZERO.angle_vectors();
+        assert_vec_eq(v.forward(), vec3_t::new(1.0, 0.0, 0.0));
+        assert_vec_eq(v.right(), vec3_t::new(0.0, -1.0, 0.0));
+        assert_vec_eq(v.up(), vec3_t::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn angle_vectors_yaw() {
+        let v = vec3_t::new(0.0, 90.0, 0.0).angle_vectors();
+        assert_vec_eq(v.forward(), vec3_t::new(0.0, 1.0, 0.0));
+        assert_vec_eq(v.right(), vec3_t::new(1.0, 0.0, 0.0));
+        assert_vec_eq(v.up(), vec3_t::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn angle_vectors_pitch() {
+        // positive pitch looks down
+        let v = vec3_t::new(90.0, 0.0, 0.0).angle_vectors();
+        assert_vec_eq(v.forward(), vec3_t::new(0.0, 0.0, -1.0));
+        assert_vec_eq(v.up(), vec3_t::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn angle_vectors_roll() {
+        let v = vec3_t::new(0.0, 0.0, 90.0).angle_vectors();
+        assert_vec_eq(v.forward(), vec3_t::new(1.0, 0.0, 0.0));
+        assert_vec_eq(v.right(), vec3_t::new(0.0, 0.0, -1.0));
+        assert_vec_eq(v.up(), vec3_t::new(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn rotate_by_angles() {
+        let angles = vec3_t::new(0.0, 90.0, 0.0);
+        let v = vec3_t::new(1.0, 2.0, 3.0);
+        assert_vec_eq(v.rotate_by_angles(angles), vec3_t::new(-2.0, 1.0, 3.0));
+        assert_vec_eq(v.rotate_by_angles(vec3_t::ZERO), v);
+    }
+}
@@ -1 +1,4 @@
-pub use crate::{entity::EntityStateExt, math::ToAngleVectors};
+pub use crate::{
+    entity::EntityStateExt,
+    math::{RotateByAngles, ToAngleVectors},
+};
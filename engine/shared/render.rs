@@ -224,6 +224,22 @@ impl RenderMode {
     pub const fn is_opaque(&self) -> bool {
         matches!(self, Self::Normal)
     }
+
+    /// Returns `true` if the mode blends with the framebuffer.
+    ///
+    /// [ScreenFadeModulate](Self::ScreenFadeModulate) multiplies the framebuffer by the source
+    /// color and is translucent too.
+    pub const fn is_translucent(&self) -> bool {
+        matches!(
+            self,
+            Self::TransColor
+                | Self::TransTexture
+                | Self::Glow
+                | Self::TransAlpha
+                | Self::TransAdd
+                | Self::ScreenFadeModulate
+        )
+    }
 }
 
 define_enum_for_primitive! {
@@ -397,3 +413,31 @@ define_ref_parm! {
     const PARM_DELUXEDATA;
     const PARM_SHADOWDATA;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_mode_raw() {
+        let modes = [
+            (RenderMode::Normal, ffi::common::kRenderNormal),
+            (RenderMode::TransColor, ffi::common::kRenderTransColor),
+            (RenderMode::TransTexture, ffi::common::kRenderTransTexture),
+            (RenderMode::Glow, ffi::common::kRenderGlow),
+            (RenderMode::TransAlpha, ffi::common::kRenderTransAlpha),
+            (RenderMode::TransAdd, ffi::common::kRenderTransAdd),
+        ];
+        for (mode, raw) in modes {
+            assert_eq!(mode.into_raw(), raw as c_int);
+            assert_eq!(RenderMode::from_raw(raw as c_int), Some(mode));
+            assert!(mode.is_opaque() != mode.is_translucent());
+        }
+
+        let mode = RenderMode::ScreenFadeModulate;
+        assert_eq!(mode.into_raw(), RENDER_SCREEN_FADE_MODULATE as c_int);
+        assert_eq!(RenderMode::from_raw(mode.into_raw()), Some(mode));
+        assert!(!mode.is_opaque());
+        assert!(mode.is_translucent());
+    }
+}